@@ -27,123 +27,612 @@
 extern crate proc_macro;
 
 use proc_macro::TokenStream;
-use quote::{quote};
+use quote::{quote, ToTokens};
+use syn::spanned::Spanned;
 use syn::{parse_macro_input, DeriveInput, Fields, Ident};
 
 /// Derive macro to make a struct "nullable" by wrapping all of its fields in `Option<T>` and
 /// generating convenient getter and setter methods for these fields.
 ///
-/// Each field's type becomes `Option<T>`, and three kinds of methods are generated for each field:
+/// Each field's type becomes `Option<T>`, and the following methods are generated for each field:
 ///
 /// - `field(&self) -> T`: A getter that returns the value or the default if the field is `None`.
 /// - `get_field(&self) -> Option<&T>`: A getter that returns the `Option<&T>`.
 /// - `set_field(&mut self, T)`: A setter that sets the field to `Some(T)`.
+/// - `with_field(self, T) -> Self`: A chainable, immutable setter for fluent construction.
+/// - `without_field(self) -> Self`: A chainable, immutable setter that clears the field.
 ///
 /// The generated struct also has a `Default` implementation that initializes all fields to `None`.
-#[proc_macro_derive(Nullable)]
+///
+/// A field (or the whole struct) can opt into generic `impl Into<T>` setters with
+/// `#[nullable(into)]`, letting callers pass e.g. `&str` where a `String` field is expected.
+///
+/// A field (or the whole struct) that is already `Option<T>` can opt into `#[nullable(strip_option)]`
+/// so the generated storage stays `Option<T>` instead of becoming `Option<Option<T>>`, with the
+/// getters and setters operating on `T` directly.
+///
+/// Tuple structs are supported too: fields get positional accessors (`field_0()`, `set_0(...)`,
+/// `get_0()`, ...) instead of name-based ones. Enums are supported by generating a companion enum
+/// with one nullable variant per input variant, plus an `Unselected` variant for "no variant chosen
+/// yet", and a `new_variant(...)` constructor per variant.
+///
+/// A struct-level `#[nullable(no_std)]` attribute switches the generated code to `core`/`alloc`
+/// paths instead of `std`, so the derive can be used in `#![no_std]` crates.
+///
+/// The generated type mirrors the input's visibility and generics (including where-clauses and
+/// bounds), and a `#[nullable(derive(Debug, Clone, PartialEq))]` attribute forwards the listed
+/// derives onto it.
+#[proc_macro_derive(Nullable, attributes(nullable))]
 pub fn nullable(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
     let name = input.ident;
     let nullable_name = Ident::new(&format!("Nullable{}", name), name.span());
+    let type_meta = TypeMeta {
+        vis: &input.vis,
+        generics: &input.generics,
+        derives: struct_derives(&input.attrs),
+    };
+
+    match input.data {
+        syn::Data::Struct(data_struct) => {
+            let struct_into = attr_has_flag(&input.attrs, "into");
+            let struct_strip_option = attr_has_flag(&input.attrs, "strip_option");
+            let no_std = attr_has_flag(&input.attrs, "no_std");
+            TokenStream::from(expand_struct(
+                &name,
+                &nullable_name,
+                &type_meta,
+                &data_struct.fields,
+                struct_into,
+                struct_strip_option,
+                no_std,
+            ))
+        }
+        syn::Data::Enum(data_enum) => {
+            let no_std = attr_has_flag(&input.attrs, "no_std");
+            TokenStream::from(expand_enum(&nullable_name, &type_meta, &data_enum, no_std))
+        }
+        syn::Data::Union(_) => panic!("Only structs and enums are nullable."),
+    }
+}
+
+/// The input struct/enum's visibility, generics, and `#[nullable(derive(...))]` list, threaded
+/// through to the generated type so it mirrors the original as closely as possible.
+struct TypeMeta<'a> {
+    vis: &'a syn::Visibility,
+    generics: &'a syn::Generics,
+    derives: Vec<syn::Path>,
+}
+
+/// The `std`/`core`/`alloc` paths used by generated code, switched by `#[nullable(no_std)]`.
+struct Paths {
+    option: proc_macro2::TokenStream,
+    result: proc_macro2::TokenStream,
+    vec: proc_macro2::TokenStream,
+    fmt: proc_macro2::TokenStream,
+    error: proc_macro2::TokenStream,
+}
+
+impl Paths {
+    fn new(no_std: bool) -> Self {
+        if no_std {
+            Paths {
+                option: quote! { core::option::Option },
+                result: quote! { core::result::Result },
+                vec: quote! { alloc::vec::Vec },
+                fmt: quote! { core::fmt },
+                error: quote! { core::error::Error },
+            }
+        } else {
+            Paths {
+                option: quote! { std::option::Option },
+                result: quote! { std::result::Result },
+                vec: quote! { std::vec::Vec },
+                fmt: quote! { std::fmt },
+                error: quote! { std::error::Error },
+            }
+        }
+    }
+}
+
+/// A single field, normalized so that named-struct fields and tuple-struct fields can share the
+/// same code path.
+struct NormalizedField<'a> {
+    /// Identifier used for the field's storage slot in the generated struct (`field1`, `field_0`, ...).
+    storage_ident: Ident,
+    /// Lowercase name used to build `set_*`/`get_*`/`with_*`/`without_*` method names. For named
+    /// fields this is the field name; for tuple fields this is the bare index (`0`, `1`, ...), so
+    /// the setter for the first tuple element is `set_0`, not `set_field_0`.
+    method_base: String,
+    field: &'a syn::Field,
+}
+
+fn normalize_fields(fields: &Fields) -> Vec<NormalizedField<'_>> {
+    fields
+        .iter()
+        .enumerate()
+        .map(|(index, field)| match &field.ident {
+            Some(ident) => NormalizedField {
+                storage_ident: ident.clone(),
+                method_base: ident.to_string().to_lowercase(),
+                field,
+            },
+            None => NormalizedField {
+                storage_ident: Ident::new(&format!("field_{}", index), field.span()),
+                method_base: index.to_string(),
+                field,
+            },
+        })
+        .collect()
+}
 
-    // Used to store tokens for each field and their initializations
-    if let syn::Data::Struct(data_struct) = input.data {
+fn expand_struct(
+    name: &Ident,
+    nullable_name: &Ident,
+    type_meta: &TypeMeta,
+    fields: &Fields,
+    struct_into: bool,
+    struct_strip_option: bool,
+    no_std: bool,
+) -> proc_macro2::TokenStream {
+    let vis = type_meta.vis;
+    let error_name = Ident::new(&format!("Nullable{}Error", name), name.span());
+    let normalized_fields = normalize_fields(fields);
+    let paths = Paths::new(no_std);
+    let option_path = &paths.option;
+    let result_path = &paths.result;
+    let vec_path = &paths.vec;
+    let fmt_path = &paths.fmt;
+    let error_path = &paths.error;
+    let (impl_generics, ty_generics, where_clause) = type_meta.generics.split_for_impl();
+    let derives = &type_meta.derives;
+    let derive_attr = if derives.is_empty() {
+        quote! {}
+    } else {
+        quote! { #[derive(#(#derives),*)] }
+    };
 
-        let generated_setters_and_getters = generate_setter_and_getter_functions(&data_struct.fields);
-        let mut field_tokens = Vec::new();
-        let mut field_def_inis = Vec::new();
-        let mut field_none_inis = Vec::new();
+    let generated_setters_and_getters =
+        generate_setter_and_getter_functions(&normalized_fields, struct_into, struct_strip_option);
 
-        let mut field_params = Vec::new();
-        let mut identifiers = Vec::new();
+    let mut field_tokens = Vec::new();
+    let mut field_def_inis = Vec::new();
+    let mut field_none_inis = Vec::new();
 
-        for field in data_struct.fields.iter() {
-            let ident = &field.ident;
-            let ty = &field.ty;
+    let mut field_params = Vec::new();
+    let mut identifiers = Vec::new();
 
-            field_tokens.push(quote! {
-                #ident: std::option::Option<#ty>,
-            });
+    let mut build_checks = Vec::new();
+    let mut build_fields = Vec::new();
 
-            field_none_inis.push(quote! {
-                #ident: None,
-            });
+    for normalized in &normalized_fields {
+        let storage_ident = &normalized.storage_ident;
+        let field = normalized.field;
+        let stripped = struct_strip_option || attr_has_flag(&field.attrs, "strip_option");
+        let ty = effective_field_type(field, struct_strip_option);
+        let ty = &ty;
+        let field_vis = &field.vis;
+
+        field_tokens.push(quote! {
+            #field_vis #storage_ident: #option_path<#ty>,
+        });
+
+        field_none_inis.push(quote! {
+            #storage_ident: None,
+        });
+
+        let default_value = match field_default_expr(field) {
+            Some(expr) => quote! { #expr },
+            None => quote! { #ty::default() },
+        };
+        field_def_inis.push(quote! {
+            #storage_ident: Some(#default_value),
+        });
+
+        field_params.push(quote! {
+            #storage_ident: #ty,
+        });
+
+        identifiers.push(quote! {
+            #storage_ident: Some(#storage_ident),
+        });
+
+        build_checks.push(quote! {
+            if self.#storage_ident.is_none() {
+                missing_fields.push(stringify!(#storage_ident));
+            }
+        });
 
-            field_def_inis.push(quote! {
-                #ident: Some(#ty::default()),
-            });
+        let reconstructed_value = if stripped && unwrap_option_type(&field.ty).is_some() {
+            quote! { Some(self.#storage_ident.unwrap()) }
+        } else {
+            quote! { self.#storage_ident.unwrap() }
+        };
+        build_fields.push(reconstructed_value);
+    }
 
-            field_params.push(quote! {
-                #ident: #ty,
-            });
+    // Generic args are left for the compiler to infer from the surrounding `Result<#name #ty_generics, _>`
+    // return type; a struct/tuple literal can't carry them here without falling back to turbofish.
+    let original_fields = match fields {
+        Fields::Named(_) => {
+            let idents = normalized_fields.iter().map(|f| &f.storage_ident);
+            quote! { #name { #(#idents: #build_fields,)* } }
+        }
+        Fields::Unnamed(_) => quote! { #name (#(#build_fields),*) },
+        Fields::Unit => quote! { #name },
+    };
 
-            identifiers.push(quote! {
-                #ident: Some(#ident),
-            });
+    quote! {
+        #derive_attr
+        #vis struct #nullable_name #impl_generics #where_clause {
+            #(#field_tokens)*
         }
 
-        let expanded = quote! {
-            pub struct #nullable_name {
-                #(#field_tokens)*
+        impl #impl_generics #nullable_name #ty_generics #where_clause {
+            pub fn new(#(#field_params)*) -> Self {
+                Self {
+                    #(#identifiers)*
+                }
             }
 
-            impl #nullable_name {
-                pub fn new(#(#field_params)*) -> Self {
-                    Self {
-                        #(#identifiers)*
-                    }
+            pub fn new_default() -> Self {
+                Self {
+                    #(#field_def_inis)*
                 }
+            }
 
-                pub fn new_default() -> Self {
-                    Self {
-                        #(#field_def_inis)*
-                    }
+            #(#generated_setters_and_getters)*
+
+            /// Converts this nullable struct back into the original struct, failing if any
+            /// field is still `None`.
+            pub fn build(self) -> #result_path<#name #ty_generics, #error_name> {
+                let mut missing_fields = #vec_path::new();
+                #(#build_checks)*
+
+                if !missing_fields.is_empty() {
+                    return #result_path::Err(#error_name { missing_fields });
                 }
 
-                #(#generated_setters_and_getters)*
+                #result_path::Ok(#original_fields)
             }
 
-            impl Default for #nullable_name {
-                fn default() -> Self {
-                    Self {
-                        #(#field_none_inis)*
-                    }
+            /// Like [`Self::build`], but panics if any field is still `None`.
+            pub fn unwrap(self) -> #name #ty_generics {
+                self.build().unwrap()
+            }
+        }
+
+        impl #impl_generics Default for #nullable_name #ty_generics #where_clause {
+            fn default() -> Self {
+                Self {
+                    #(#field_none_inis)*
                 }
             }
-        };
+        }
+
+        /// Error returned by the nullable struct's `build()` when one or more fields are still `None`.
+        #[derive(Debug)]
+        #vis struct #error_name {
+            pub missing_fields: #vec_path<&'static str>,
+        }
+
+        impl #fmt_path::Display for #error_name {
+            fn fmt(&self, f: &mut #fmt_path::Formatter<'_>) -> #fmt_path::Result {
+                write!(f, "missing required fields: {}", self.missing_fields.join(", "))
+            }
+        }
 
-        return TokenStream::from(expanded);
+        impl #error_path for #error_name {}
     }
+}
+
+/// Generates a nullable companion enum with one variant per input variant (mirroring its shape,
+/// with each field wrapped in `Option<T>`), plus an `Unselected` variant for "no variant chosen
+/// yet", and a `new_<variant>(...)` constructor per variant.
+fn expand_enum(
+    nullable_name: &Ident,
+    type_meta: &TypeMeta,
+    data_enum: &syn::DataEnum,
+    no_std: bool,
+) -> proc_macro2::TokenStream {
+    let vis = type_meta.vis;
+    let (impl_generics, ty_generics, where_clause) = type_meta.generics.split_for_impl();
+    let derives = &type_meta.derives;
+    let derive_attr = if derives.is_empty() {
+        quote! {}
+    } else {
+        quote! { #[derive(#(#derives),*)] }
+    };
+    let option_path = &Paths::new(no_std).option;
+    let mut variant_tokens = Vec::new();
+    let mut constructors = Vec::new();
+
+    for variant in &data_enum.variants {
+        let variant_ident = &variant.ident;
+        let normalized_fields = normalize_fields(&variant.fields);
+        let constructor_name = Ident::new(
+            &format!("new_{}", variant_ident.to_string().to_lowercase()),
+            variant_ident.span(),
+        );
+
+        match &variant.fields {
+            Fields::Named(_) => {
+                let decls = normalized_fields.iter().map(|f| {
+                    let ident = &f.storage_ident;
+                    let ty = &f.field.ty;
+                    quote! { #ident: #option_path<#ty> }
+                });
+                variant_tokens.push(quote! { #variant_ident { #(#decls,)* } });
 
-    panic!("Only structs are nullable.");
+                let params = normalized_fields.iter().map(|f| {
+                    let ident = &f.storage_ident;
+                    let ty = &f.field.ty;
+                    quote! { #ident: #ty }
+                });
+                let inits = normalized_fields
+                    .iter()
+                    .map(|f| {
+                        let ident = &f.storage_ident;
+                        quote! { #ident: Some(#ident) }
+                    });
+                constructors.push(quote! {
+                    pub fn #constructor_name(#(#params),*) -> Self {
+                        Self::#variant_ident { #(#inits,)* }
+                    }
+                });
+            }
+            Fields::Unnamed(_) => {
+                let tys = normalized_fields.iter().map(|f| {
+                    let ty = &f.field.ty;
+                    quote! { #option_path<#ty> }
+                });
+                variant_tokens.push(quote! { #variant_ident(#(#tys),*) });
+
+                let param_idents: Vec<Ident> = normalized_fields
+                    .iter()
+                    .map(|f| f.storage_ident.clone())
+                    .collect();
+                let params = param_idents.iter().zip(normalized_fields.iter()).map(|(ident, f)| {
+                    let ty = &f.field.ty;
+                    quote! { #ident: #ty }
+                });
+                constructors.push(quote! {
+                    pub fn #constructor_name(#(#params),*) -> Self {
+                        Self::#variant_ident(#(Some(#param_idents)),*)
+                    }
+                });
+            }
+            Fields::Unit => {
+                variant_tokens.push(quote! { #variant_ident });
+                constructors.push(quote! {
+                    pub fn #constructor_name() -> Self {
+                        Self::#variant_ident
+                    }
+                });
+            }
+        }
+    }
+
+    quote! {
+        #derive_attr
+        #vis enum #nullable_name #impl_generics #where_clause {
+            /// No variant has been selected yet.
+            Unselected,
+            #(#variant_tokens,)*
+        }
+
+        impl #impl_generics #nullable_name #ty_generics #where_clause {
+            #(#constructors)*
+        }
+
+        impl #impl_generics Default for #nullable_name #ty_generics #where_clause {
+            fn default() -> Self {
+                Self::Unselected
+            }
+        }
+    }
 }
 
-fn generate_setter_and_getter_functions(fields: &Fields) -> Vec<proc_macro2::TokenStream> {
+fn generate_setter_and_getter_functions(
+    fields: &[NormalizedField<'_>],
+    struct_into: bool,
+    struct_strip_option: bool,
+) -> Vec<proc_macro2::TokenStream> {
     fields.iter().map(
-        |field| {
-            let ty = &field.ty;
-            let ident = field.ident.as_ref().expect("No identifier found for type");
-            let setter_name = Ident::new(
-                &format!("set_{}", ident.to_string().to_lowercase()),
-                ident.span(),
-            );
-            let getter_name = Ident::new(
-                &format!("get_{}", ident.to_string().to_lowercase()),
-                ident.span(),
-            );
+        |normalized| {
+            let field = normalized.field;
+            let storage_ident = &normalized.storage_ident;
+            let method_base = &normalized.method_base;
+            let ty = effective_field_type(field, struct_strip_option);
+            let ty = &ty;
+
+            let accessor_name = storage_ident.clone();
+            let setter_name = Ident::new(&format!("set_{}", method_base), storage_ident.span());
+            let getter_name = Ident::new(&format!("get_{}", method_base), storage_ident.span());
+            let with_name = Ident::new(&format!("with_{}", method_base), storage_ident.span());
+            let without_name = Ident::new(&format!("without_{}", method_base), storage_ident.span());
+
+            let getter_body = match field_default_expr(field) {
+                Some(expr) => quote! { self.#storage_ident.clone().unwrap_or_else(|| #expr) },
+                None => quote! { self.#storage_ident.clone().unwrap_or_default() },
+            };
+
+            let use_into = struct_into || attr_has_flag(&field.attrs, "into");
+
+            let setters = if use_into {
+                quote! {
+                    pub fn #setter_name<T: Into<#ty>>(&mut self, value: T) {
+                        self.#storage_ident = Some(value.into())
+                    }
+
+                    pub fn #with_name<T: Into<#ty>>(mut self, value: T) -> Self {
+                        self.#storage_ident = Some(value.into());
+                        self
+                    }
+                }
+            } else {
+                quote! {
+                    pub fn #setter_name(&mut self, value: #ty) {
+                        self.#storage_ident = Some(value)
+                    }
+
+                    pub fn #with_name(mut self, value: #ty) -> Self {
+                        self.#storage_ident = Some(value);
+                        self
+                    }
+                }
+            };
 
             quote! {
-                pub fn #ident(&self) -> #ty {
-                    self.#ident.clone().unwrap_or_default()
+                pub fn #accessor_name(&self) -> #ty {
+                    #getter_body
                 }
 
                 pub fn #getter_name(&self) -> Option<&#ty> {
-                    self.#ident.as_ref()
+                    self.#storage_ident.as_ref()
                 }
 
-                pub fn #setter_name(&mut self, value: #ty) {
-                    self.#ident = Some(value)
+                #setters
+
+                pub fn #without_name(mut self) -> Self {
+                    self.#storage_ident = None;
+                    self
                 }
             }
         }).collect()
 }
+
+/// Returns `true` if `attrs` contains a bare `#[nullable(flag)]` entry, e.g. `#[nullable(into)]`.
+fn attr_has_flag(attrs: &[syn::Attribute], flag: &str) -> bool {
+    let mut found = false;
+
+    for attr in attrs {
+        if !attr.path().is_ident("nullable") {
+            continue;
+        }
+
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident(flag) {
+                found = true;
+            } else {
+                skip_unhandled_meta_value(&meta)?;
+            }
+            Ok(())
+        }).expect("invalid #[nullable(...)] attribute");
+    }
+
+    found
+}
+
+/// Returns the `syn::Path`s listed in a `#[nullable(derive(Debug, Clone, ...))]` attribute.
+fn struct_derives(attrs: &[syn::Attribute]) -> Vec<syn::Path> {
+    let mut derives = Vec::new();
+
+    for attr in attrs {
+        if !attr.path().is_ident("nullable") {
+            continue;
+        }
+
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("derive") {
+                meta.parse_nested_meta(|inner| {
+                    derives.push(inner.path.clone());
+                    Ok(())
+                })?;
+            } else {
+                skip_unhandled_meta_value(&meta)?;
+            }
+            Ok(())
+        }).expect("invalid #[nullable(...)] attribute");
+    }
+
+    derives
+}
+
+/// Consumes the `= value` or `(...)` tail of a `nullable` meta item this parser doesn't recognize,
+/// so an attribute mixing several keys (e.g. `#[nullable(into, derive(Debug))]`) doesn't trip over
+/// keys meant for a different helper.
+fn skip_unhandled_meta_value(meta: &syn::meta::ParseNestedMeta) -> syn::Result<()> {
+    if meta.input.peek(syn::Token![=]) {
+        let _: syn::Lit = meta.value()?.parse()?;
+    } else if meta.input.peek(syn::token::Paren) {
+        let content;
+        syn::parenthesized!(content in meta.input);
+        let _: proc_macro2::TokenStream = content.parse()?;
+    }
+    Ok(())
+}
+
+/// Returns the type that should back a field's storage and its getters/setters.
+///
+/// Normally this is just the field's declared type. But when `strip_option` applies (either via
+/// the struct-level or the field-level `#[nullable(strip_option)]` attribute) and the field is
+/// already `Option<T>`, this returns the inner `T` so the generated struct stores `Option<T>`
+/// instead of double-wrapping it as `Option<Option<T>>`.
+fn effective_field_type(field: &syn::Field, struct_strip_option: bool) -> syn::Type {
+    let strip_option = struct_strip_option || attr_has_flag(&field.attrs, "strip_option");
+
+    if strip_option {
+        if let Some(inner) = unwrap_option_type(&field.ty) {
+            return inner;
+        }
+    }
+
+    field.ty.clone()
+}
+
+/// If `ty` is `Option<T>`, returns `T`; otherwise returns `None`.
+fn unwrap_option_type(ty: &syn::Type) -> Option<syn::Type> {
+    let syn::Type::Path(type_path) = ty else {
+        return None;
+    };
+    let segment = type_path.path.segments.last()?;
+    if segment.ident != "Option" {
+        return None;
+    }
+    let syn::PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    if args.args.len() != 1 {
+        return None;
+    }
+    match &args.args[0] {
+        syn::GenericArgument::Type(inner) => Some(inner.clone()),
+        _ => None,
+    }
+}
+
+/// Reads a field's `#[nullable(default = ...)]` attribute, if present, and returns the
+/// expression that should be used in place of `T::default()`.
+///
+/// The attribute accepts either a quoted Rust expression (`default = "retry_count * 2"`) or a
+/// bare literal (`default = 30`).
+fn field_default_expr(field: &syn::Field) -> Option<proc_macro2::TokenStream> {
+    for attr in &field.attrs {
+        if !attr.path().is_ident("nullable") {
+            continue;
+        }
+
+        let mut default_expr = None;
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("default") {
+                let lit: syn::Lit = meta.value()?.parse()?;
+                default_expr = Some(match lit {
+                    syn::Lit::Str(s) => syn::parse_str::<syn::Expr>(&s.value())
+                        .expect("invalid expression in #[nullable(default = ...)]")
+                        .into_token_stream(),
+                    other => other.into_token_stream(),
+                });
+            } else {
+                skip_unhandled_meta_value(&meta)?;
+            }
+            Ok(())
+        }).expect("invalid #[nullable(...)] attribute");
+
+        if default_expr.is_some() {
+            return default_expr;
+        }
+    }
+
+    None
+}
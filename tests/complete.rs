@@ -1,6 +1,10 @@
 // Bring the procedural macro into scope
 use nullable_struct::Nullable;
 
+// `#[nullable(no_std)]` generates `alloc::vec::Vec` paths, so pull in `alloc` for these tests
+// even though this test binary otherwise links `std`.
+extern crate alloc;
+
 #[derive(Nullable)]
 struct TestStruct {
     field1: i32,
@@ -47,4 +51,255 @@ fn test_default() {
     let s = NullableTestStruct::default();
     assert_eq!(s.field1(), 0); // Default for i32 is 0
     assert_eq!(s.field2(), ""); // Default for String is ""
+}
+
+#[derive(Nullable)]
+struct StructWithDefaults {
+    #[nullable(default = 30)]
+    retry_count: i32,
+    #[nullable(default = "10 * 2")]
+    max_retry_count: i32,
+}
+
+#[test]
+fn test_custom_defaults() {
+    let s = NullableStructWithDefaults::default();
+    assert_eq!(s.retry_count(), 30);
+    assert_eq!(s.max_retry_count(), 20);
+
+    let built = NullableStructWithDefaults::new_default().build().unwrap();
+    assert_eq!(built.retry_count, 30);
+    assert_eq!(built.max_retry_count, 20);
+}
+
+#[test]
+fn test_new_default_with_custom_defaults() {
+    let s = NullableStructWithDefaults::new_default();
+    assert_eq!(s.retry_count(), 30);
+    assert_eq!(s.max_retry_count(), 20);
+}
+
+#[test]
+fn test_with_and_without() {
+    let s = NullableTestStruct::default()
+        .with_field1(42)
+        .with_field2("hello".to_string());
+    assert_eq!(s.field1(), 42);
+    assert_eq!(s.field2(), "hello".to_string());
+
+    let s = s.without_field1();
+    assert_eq!(s.get_field1(), None);
+}
+
+#[derive(Nullable)]
+struct StructWithInto {
+    #[nullable(into)]
+    name: String,
+    age: i32,
+}
+
+#[test]
+fn test_field_level_into() {
+    let mut s = NullableStructWithInto::default();
+    s.set_name("hello");
+    assert_eq!(s.name(), "hello".to_string());
+
+    let s = NullableStructWithInto::default().with_name("world");
+    assert_eq!(s.name(), "world".to_string());
+
+    let s = NullableStructWithInto::default().with_name("world").with_age(1);
+    let built = s.build().unwrap();
+    assert_eq!(built.name, "world".to_string());
+    assert_eq!(built.age, 1);
+}
+
+#[derive(Nullable)]
+#[nullable(into)]
+struct StructWithStructLevelInto {
+    name: String,
+    nickname: String,
+}
+
+#[test]
+fn test_struct_level_into() {
+    let s = NullableStructWithStructLevelInto::default()
+        .with_name("hello")
+        .with_nickname("world");
+    assert_eq!(s.name(), "hello".to_string());
+    assert_eq!(s.nickname(), "world".to_string());
+
+    let built = s.build().unwrap();
+    assert_eq!(built.name, "hello".to_string());
+    assert_eq!(built.nickname, "world".to_string());
+}
+
+#[derive(Nullable)]
+struct StructWithOptionField {
+    #[nullable(strip_option)]
+    nickname: Option<String>,
+    age: i32,
+}
+
+#[test]
+fn test_strip_option() {
+    let mut s = NullableStructWithOptionField::default();
+    assert_eq!(s.get_nickname(), None);
+
+    s.set_nickname("hi".to_string());
+    assert_eq!(s.nickname(), "hi".to_string());
+    assert_eq!(s.get_nickname(), Some(&"hi".to_string()));
+
+    let s = s.without_nickname();
+    assert_eq!(s.get_nickname(), None);
+
+    let s = s.with_nickname("hi".to_string()).with_age(30);
+    let built = s.build().unwrap();
+    assert_eq!(built.nickname, Some("hi".to_string()));
+    assert_eq!(built.age, 30);
+}
+
+#[test]
+fn test_build_ok() {
+    let s = NullableTestStruct::new(42, "hello".to_string());
+    let built = s.build().unwrap();
+    assert_eq!(built.field1, 42);
+    assert_eq!(built.field2, "hello".to_string());
+}
+
+#[test]
+fn test_build_missing_fields() {
+    let s = NullableTestStruct::default().with_field1(42);
+    let err = match s.build() {
+        Ok(_) => panic!("expected build() to fail with a missing field"),
+        Err(err) => err,
+    };
+    assert_eq!(err.missing_fields, vec!["field2"]);
+}
+
+#[test]
+fn test_unwrap() {
+    let s = NullableTestStruct::new(42, "hello".to_string());
+    let built = s.unwrap();
+    assert_eq!(built.field1, 42);
+    assert_eq!(built.field2, "hello".to_string());
+}
+
+#[derive(Nullable)]
+struct TupleStruct(i32, String);
+
+#[test]
+fn test_tuple_struct() {
+    let mut s = NullableTupleStruct::new(42, "hello".to_string());
+    assert_eq!(s.field_0(), 42);
+    assert_eq!(s.field_1(), "hello".to_string());
+
+    s.set_0(13);
+    s.set_1("world".to_string());
+    assert_eq!(s.get_0(), Some(&13));
+    assert_eq!(s.get_1(), Some(&"world".to_string()));
+
+    let built = s.build().unwrap();
+    assert_eq!(built.0, 13);
+    assert_eq!(built.1, "world".to_string());
+}
+
+#[derive(Nullable)]
+enum Shape {
+    Circle { radius: f64 },
+    Rectangle(f64, f64),
+    Empty,
+}
+
+#[test]
+fn test_enum_variants() {
+    let default = NullableShape::default();
+    assert!(matches!(default, NullableShape::Unselected));
+
+    let circle = match NullableShape::new_circle(2.0) {
+        NullableShape::Circle { radius } => {
+            assert_eq!(radius, Some(2.0));
+            Shape::Circle { radius: radius.unwrap() }
+        }
+        _ => panic!("expected Circle"),
+    };
+    assert!(matches!(circle, Shape::Circle { radius } if radius == 2.0));
+
+    let rectangle = match NullableShape::new_rectangle(2.0, 3.0) {
+        NullableShape::Rectangle(w, h) => {
+            assert_eq!(w, Some(2.0));
+            assert_eq!(h, Some(3.0));
+            Shape::Rectangle(w.unwrap(), h.unwrap())
+        }
+        _ => panic!("expected Rectangle"),
+    };
+    assert!(matches!(rectangle, Shape::Rectangle(2.0, 3.0)));
+
+    let empty = match NullableShape::new_empty() {
+        NullableShape::Empty => Shape::Empty,
+        _ => panic!("expected Empty"),
+    };
+    assert!(matches!(empty, Shape::Empty));
+}
+
+#[derive(Nullable)]
+#[nullable(no_std)]
+struct NoStdStruct {
+    field1: i32,
+    field2: String,
+}
+
+#[test]
+fn test_no_std_struct() {
+    let s = NullableNoStdStruct::new(42, "hello".to_string());
+    assert_eq!(s.field1(), 42);
+    assert_eq!(s.field2(), "hello".to_string());
+
+    let built = s.build().unwrap();
+    assert_eq!(built.field1, 42);
+    assert_eq!(built.field2, "hello".to_string());
+}
+
+#[derive(Nullable)]
+#[nullable(no_std)]
+enum NoStdEnum {
+    A(i32),
+    B,
+}
+
+#[test]
+fn test_no_std_enum() {
+    let a = match NullableNoStdEnum::new_a(42) {
+        NullableNoStdEnum::A(value) => NoStdEnum::A(value.unwrap()),
+        _ => panic!("expected A"),
+    };
+    assert!(matches!(a, NoStdEnum::A(42)));
+
+    let b = match NullableNoStdEnum::new_b() {
+        NullableNoStdEnum::B => NoStdEnum::B,
+        _ => panic!("expected B"),
+    };
+    assert!(matches!(b, NoStdEnum::B));
+}
+
+#[derive(Nullable)]
+#[nullable(derive(Debug, Clone, PartialEq))]
+struct GenericStruct<T: Clone + Default> {
+    value: T,
+    label: String,
+}
+
+#[test]
+fn test_generics_and_derives() {
+    let s = NullableGenericStruct::new(42, "hello".to_string());
+    assert_eq!(s.value(), 42);
+    assert_eq!(s.label(), "hello".to_string());
+
+    // Forwarded derives: this wouldn't compile without Debug/Clone/PartialEq on the generated type.
+    let cloned = s.clone();
+    assert_eq!(s, cloned);
+    assert!(!format!("{:?}", s).is_empty());
+
+    let built = s.build().unwrap();
+    assert_eq!(built.value, 42);
+    assert_eq!(built.label, "hello".to_string());
 }
\ No newline at end of file